@@ -1,3 +1,4 @@
+use std::collections::{BTreeSet, VecDeque};
 use std::fmt::Debug;
 
 fn main() {
@@ -37,6 +38,82 @@ impl<Q, A, F> Sequence<Q, A, F>
 Q: Clone + PartialEq + Debug,
 A: PartialEq + Debug + Clone,
 F: Fn(Q,&A) -> Option<Q> + Clone {
+    // Renders this run as Graphviz DOT, drawing the whole machine but
+    // highlighting the path `is_accepted` actually took: visited states are
+    // filled, the traversed edges are drawn in red and labelled with the symbol
+    // that was consumed along them, and the graph is captioned with the verdict.
+    // It is the visual counterpart to `print_and_accept` for debugging why a
+    // string was accepted or rejected.
+    pub fn to_dot(&self) -> String {
+        let dfa = &self.dfa;
+        // The states the run actually visited, in first-seen order.
+        let mut visited: Vec<usize> = Vec::new();
+        for (q, _) in self.sequence.iter() {
+            if let Some(i) = dfa.states.iter().position(|s| s == q) {
+                if !visited.contains(&i) {
+                    visited.push(i);
+                }
+            }
+        }
+        // The edges taken, read off consecutive sequence entries. The label of an
+        // entry is the symbol consumed to reach it. The first start entry is never
+        // the target of a window, and the second carries an empty label, so the
+        // window ending at it is skipped.
+        let mut traversed: Vec<(usize, usize, String)> = Vec::new();
+        for w in self.sequence.windows(2) {
+            let (from, _) = &w[0];
+            let (to, label) = &w[1];
+            if label.is_empty() {
+                continue;
+            }
+            if let (Some(i), Some(j)) = (
+                dfa.states.iter().position(|s| s == from),
+                dfa.states.iter().position(|s| s == to),
+            ) {
+                traversed.push((i, j, label.clone()));
+            }
+        }
+        let traversed_pairs: Vec<(usize, usize)> =
+            traversed.iter().map(|(i, j, _)| (*i, *j)).collect();
+
+        let mut out = String::from("digraph DFA {\n    rankdir=LR;\n");
+        out.push_str(&format!(
+            "    label=\"{}\";\n",
+            if self.accept { "accepted" } else { "rejected" }));
+        out.push_str("    __start [shape=point, style=invis];\n");
+        for (i, q) in dfa.states.iter().enumerate() {
+            let shape = if dfa.final_states.contains(q) { "doublecircle" } else { "circle" };
+            if visited.contains(&i) {
+                out.push_str(&format!(
+                    "    n{} [shape={}, style=filled, fillcolor=lightblue, label=\"{}\"];\n",
+                    i, shape, dot_escape(&format!("{:?}", q))));
+            } else {
+                out.push_str(&format!(
+                    "    n{} [shape={}, label=\"{}\"];\n",
+                    i, shape, dot_escape(&format!("{:?}", q))));
+            }
+        }
+        if let Some(s) = dfa.states.iter().position(|q| q == &dfa.start_state) {
+            out.push_str(&format!("    __start -> n{};\n", s));
+        }
+        // The machine's other transitions, drawn plainly.
+        for ((i, j), syms) in dfa.transitions_grouped() {
+            if traversed_pairs.contains(&(i, j)) {
+                continue;
+            }
+            out.push_str(&format!(
+                "    n{} -> n{} [label=\"{}\"];\n", i, j, dot_escape(&syms.join(","))));
+        }
+        // The traversed edges, in red, annotated with the consumed symbol.
+        for (i, j, label) in traversed {
+            out.push_str(&format!(
+                "    n{} -> n{} [label=\"{}\", color=red, fontcolor=red, penwidth=2.0];\n",
+                i, j, dot_escape(&label)));
+        }
+        out.push_str("}\n");
+        out
+    }
+
     pub fn print_and_accept(&self) -> bool {
         if self.accept {
             println!("accept on");
@@ -123,8 +200,907 @@ F: Fn(Q,&A) -> Option<Q> + Clone
                 final_states,
             }
         }
+
+        // Returns an equivalent DFA with the fewest possible states, via
+        // Hopcroft's partition-refinement. We first complete the machine by
+        // routing every undefined `(state, symbol)` to an explicit dead/sink
+        // state so Delta becomes total, then refine the partition {final,
+        // non-final} using a worklist of `(block, symbol)` splitters until no
+        // block can be split any further. Each surviving block becomes a single
+        // state of the minimized DFA (its states carry the original states they
+        // merge, so the result stays inspectable); the block holding q0 is the
+        // start state and any block containing an original final state is final.
+        pub fn minimize(&self)
+            -> DeterministicFiniteAutomaton<
+                Vec<Q>,
+                A,
+                impl Fn(Vec<Q>,&A) -> Option<Vec<Q>> + Clone> {
+            let n = self.states.len();
+            let dead = n; // index of the added sink state
+            let alphabet = &self.input_alphabet;
+            // Totalise Delta over the indices 0..=dead.
+            let mut delta: Vec<Vec<usize>> = vec![vec![dead; alphabet.len()]; n + 1];
+            for (i, q) in self.states.iter().enumerate() {
+                for (j, a) in alphabet.iter().enumerate() {
+                    delta[i][j] = match (self.transition_function)(q.clone(), a) {
+                        Some(next) => self.states.iter()
+                            .position(|s| s == &next)
+                            .unwrap_or(dead),
+                        None => dead,
+                    };
+                }
+            }
+
+            // Only states reachable from q0 matter; an already-total machine
+            // never reaches the added sink, so this also keeps it out of the
+            // result. BFS over the totalised Delta.
+            let start_index = self.states.iter()
+                .position(|s| s == &self.start_state)
+                .unwrap();
+            let mut reachable: BTreeSet<usize> = BTreeSet::from([start_index]);
+            let mut frontier: VecDeque<usize> = reachable.iter().cloned().collect();
+            while let Some(s) = frontier.pop_front() {
+                for j in 0..alphabet.len() {
+                    if reachable.insert(delta[s][j]) {
+                        frontier.push_back(delta[s][j]);
+                    }
+                }
+            }
+
+            // Initial partition: final states versus everyone else (the sink is
+            // non-final and so joins the second block).
+            let mut finals: BTreeSet<usize> = BTreeSet::new();
+            let mut others: BTreeSet<usize> = BTreeSet::new();
+            for &i in reachable.iter() {
+                if i < n && self.final_states.contains(&self.states[i]) {
+                    finals.insert(i);
+                } else {
+                    others.insert(i);
+                }
+            }
+            let mut partition: Vec<BTreeSet<usize>> =
+                [finals, others].into_iter().filter(|b| !b.is_empty()).collect();
+            let mut worklist: VecDeque<(BTreeSet<usize>, usize)> = VecDeque::new();
+            for block in partition.iter() {
+                for j in 0..alphabet.len() {
+                    worklist.push_back((block.clone(), j));
+                }
+            }
+
+            while let Some((splitter, j)) = worklist.pop_front() {
+                // X is the set of states whose `a`-transition lands inside the
+                // splitter block.
+                let x: BTreeSet<usize> = reachable.iter().cloned()
+                    .filter(|&s| splitter.contains(&delta[s][j]))
+                    .collect();
+                let mut refined: Vec<BTreeSet<usize>> = Vec::new();
+                for block in partition.into_iter() {
+                    let inter: BTreeSet<usize> = block.intersection(&x).cloned().collect();
+                    let diff: BTreeSet<usize> = block.difference(&x).cloned().collect();
+                    if inter.is_empty() || diff.is_empty() {
+                        refined.push(block);
+                        continue;
+                    }
+                    // Y splits into `inter` and `diff`; replace the queued (Y, a)
+                    // entries if present, otherwise enqueue the smaller part.
+                    for sym in 0..alphabet.len() {
+                        if let Some(pos) = worklist.iter().position(|(b, s)| b == &block && *s == sym) {
+                            worklist.remove(pos);
+                            worklist.push_back((inter.clone(), sym));
+                            worklist.push_back((diff.clone(), sym));
+                        } else if inter.len() <= diff.len() {
+                            worklist.push_back((inter.clone(), sym));
+                        } else {
+                            worklist.push_back((diff.clone(), sym));
+                        }
+                    }
+                    refined.push(inter);
+                    refined.push(diff);
+                }
+                partition = refined;
+            }
+
+            // Map each state index to the position of its block, and materialise
+            // every block as the vector of original states it contains.
+            let block_of = |state: usize| -> usize {
+                partition.iter().position(|b| b.contains(&state)).unwrap()
+            };
+            let block_states = |block: &BTreeSet<usize>| -> Vec<Q> {
+                block.iter().filter(|&&i| i < n).map(|&i| self.states[i].clone()).collect()
+            };
+            let out_states: Vec<Vec<Q>> = partition.iter().map(block_states).collect();
+
+            let start = out_states[block_of(start_index)].clone();
+            let final_states: Vec<Vec<Q>> = partition.iter().enumerate()
+                .filter(|(_, b)| b.iter().any(|&i| i < n && self.final_states.contains(&self.states[i])))
+                .map(|(bi, _)| out_states[bi].clone())
+                .collect();
+
+            let mut table: Vec<(Vec<Q>, A, Vec<Q>)> = Vec::new();
+            for (bi, block) in partition.iter().enumerate() {
+                let rep = *block.iter().next().unwrap();
+                for (j, a) in alphabet.iter().enumerate() {
+                    let target = out_states[block_of(delta[rep][j])].clone();
+                    table.push((out_states[bi].clone(), a.clone(), target));
+                }
+            }
+
+            let input_alphabet = self.input_alphabet.clone();
+            DeterministicFiniteAutomaton::new(
+                out_states,
+                input_alphabet,
+                move |state: Vec<Q>, symbol: &A| {
+                    table.iter()
+                        .find(|(s, a, _)| *s == state && a == symbol)
+                        .map(|(_, _, target)| target.clone())
+                },
+                start,
+                final_states,
+            )
+        }
+
+        // The product construction shared by `union` and `intersection`. A state
+        // of the product is a pair of component states, each wrapped in an
+        // `Option` so that `None` represents that component having fallen into its
+        // dead/sink state (reached when its own Delta returned `None`). Both
+        // components step in lockstep on every symbol, so the product is total; we
+        // only materialise the states actually reachable from the pair of start
+        // states. A product state is final when `combine` of the two component
+        // final predicates holds.
+        fn product<Q2, F2>(
+            &self,
+            other: &DeterministicFiniteAutomaton<Q2, A, F2>,
+            combine: fn(bool, bool) -> bool,
+        ) -> DeterministicFiniteAutomaton<
+                (Option<Q>, Option<Q2>),
+                A,
+                impl Fn((Option<Q>,Option<Q2>),&A) -> Option<(Option<Q>,Option<Q2>)> + Clone>
+        where
+            Q2: Clone + PartialEq + Debug,
+            F2: Fn(Q2,&A) -> Option<Q2> + Clone {
+            // The product runs over the union of the two alphabets.
+            let mut input_alphabet = self.input_alphabet.clone();
+            for a in other.input_alphabet.iter() {
+                if !input_alphabet.contains(a) {
+                    input_alphabet.push(a.clone());
+                }
+            }
+
+            let left = self.transition_function.clone();
+            let right = other.transition_function.clone();
+            let self_finals = self.final_states.clone();
+            let other_finals = other.final_states.clone();
+
+            let start = (Some(self.start_state.clone()), Some(other.start_state.clone()));
+            let mut states: Vec<(Option<Q>, Option<Q2>)> = vec![start.clone()];
+            let mut worklist: VecDeque<(Option<Q>, Option<Q2>)> =
+                VecDeque::from([start.clone()]);
+            let mut table: Vec<((Option<Q>,Option<Q2>), A, (Option<Q>,Option<Q2>))> = Vec::new();
+            while let Some((l, r)) = worklist.pop_front() {
+                for a in input_alphabet.iter() {
+                    let nl = l.as_ref().and_then(|q| left(q.clone(), a));
+                    let nr = r.as_ref().and_then(|q| right(q.clone(), a));
+                    let target = (nl, nr);
+                    if !states.contains(&target) {
+                        states.push(target.clone());
+                        worklist.push_back(target.clone());
+                    }
+                    table.push(((l.clone(), r.clone()), a.clone(), target));
+                }
+            }
+            let final_states: Vec<(Option<Q>, Option<Q2>)> = states.iter()
+                .filter(|(l, r)| {
+                    let lf = l.as_ref().map_or(false, |q| self_finals.contains(q));
+                    let rf = r.as_ref().map_or(false, |q| other_finals.contains(q));
+                    combine(lf, rf)
+                })
+                .cloned()
+                .collect();
+            DeterministicFiniteAutomaton::new(
+                states,
+                input_alphabet,
+                move |state: (Option<Q>,Option<Q2>), symbol: &A| {
+                    table.iter()
+                        .find(|(s, a, _)| *s == state && a == symbol)
+                        .map(|(_, _, target)| target.clone())
+                },
+                start,
+                final_states,
+            )
+        }
+
+        // The DFA for the intersection of the two languages: a string is accepted
+        // iff both machines accept it.
+        pub fn intersection<Q2, F2>(
+            &self,
+            other: &DeterministicFiniteAutomaton<Q2, A, F2>,
+        ) -> DeterministicFiniteAutomaton<
+                (Option<Q>, Option<Q2>),
+                A,
+                impl Fn((Option<Q>,Option<Q2>),&A) -> Option<(Option<Q>,Option<Q2>)> + Clone>
+        where
+            Q2: Clone + PartialEq + Debug,
+            F2: Fn(Q2,&A) -> Option<Q2> + Clone {
+            self.product(other, |l, r| l && r)
+        }
+
+        // The DFA for the union of the two languages: a string is accepted iff
+        // either machine accepts it.
+        pub fn union<Q2, F2>(
+            &self,
+            other: &DeterministicFiniteAutomaton<Q2, A, F2>,
+        ) -> DeterministicFiniteAutomaton<
+                (Option<Q>, Option<Q2>),
+                A,
+                impl Fn((Option<Q>,Option<Q2>),&A) -> Option<(Option<Q>,Option<Q2>)> + Clone>
+        where
+            Q2: Clone + PartialEq + Debug,
+            F2: Fn(Q2,&A) -> Option<Q2> + Clone {
+            self.product(other, |l, r| l || r)
+        }
+
+        // The DFA for the complement language. We first totalise the machine by
+        // routing every undefined transition to an explicit sink (`None`), which
+        // also loops to itself, and then flip the accepting condition: a state is
+        // final iff the original state was *not* final (the sink, being non-final
+        // originally, becomes accepting).
+        pub fn complement(&self)
+            -> DeterministicFiniteAutomaton<
+                Option<Q>,
+                A,
+                impl Fn(Option<Q>,&A) -> Option<Option<Q>> + Clone> {
+            let delta = self.transition_function.clone();
+            let self_finals = self.final_states.clone();
+            let input_alphabet = self.input_alphabet.clone();
+
+            let start = Some(self.start_state.clone());
+            let mut states: Vec<Option<Q>> = vec![start.clone()];
+            let mut worklist: VecDeque<Option<Q>> = VecDeque::from([start.clone()]);
+            let mut table: Vec<(Option<Q>, A, Option<Q>)> = Vec::new();
+            while let Some(q) = worklist.pop_front() {
+                for a in input_alphabet.iter() {
+                    let target = q.as_ref().and_then(|s| delta(s.clone(), a));
+                    if !states.contains(&target) {
+                        states.push(target.clone());
+                        worklist.push_back(target.clone());
+                    }
+                    table.push((q.clone(), a.clone(), target));
+                }
+            }
+            let final_states: Vec<Option<Q>> = states.iter()
+                .filter(|q| !q.as_ref().map_or(false, |s| self_finals.contains(s)))
+                .cloned()
+                .collect();
+            DeterministicFiniteAutomaton::new(
+                states,
+                input_alphabet,
+                move |state: Option<Q>, symbol: &A| {
+                    table.iter()
+                        .find(|(s, a, _)| *s == state && a == symbol)
+                        .map(|(_, _, target)| target.clone())
+                },
+                start,
+                final_states,
+            )
+        }
+
+        // The DFA accepting every string that *starts with* a string of this
+        // machine's language. Once a run reaches a final state the match is
+        // locked in, so we send it to a permanent accepting sink (`None`) that
+        // loops to itself regardless of further input. Strings that end exactly on
+        // one of the original final states are still accepted.
+        pub fn starts_with(&self)
+            -> DeterministicFiniteAutomaton<
+                Option<Q>,
+                A,
+                impl Fn(Option<Q>,&A) -> Option<Option<Q>> + Clone> {
+            let delta = self.transition_function.clone();
+            let self_finals = self.final_states.clone();
+            let input_alphabet = self.input_alphabet.clone();
+
+            let start = Some(self.start_state.clone());
+            let mut states: Vec<Option<Q>> = vec![start.clone()];
+            let mut worklist: VecDeque<Option<Q>> = VecDeque::from([start.clone()]);
+            let mut table: Vec<(Option<Q>, A, Option<Q>)> = Vec::new();
+            while let Some(q) = worklist.pop_front() {
+                for a in input_alphabet.iter() {
+                    // The accepting sink, and any already-matched state, absorb all
+                    // further input into the sink. Everywhere else we defer to the
+                    // original Delta; an undefined move there stays undefined (the
+                    // outer `Option` distinguishes "no transition" from "the
+                    // accepting sink", which the inner `None` denotes).
+                    let target: Option<Option<Q>> = match &q {
+                        None => Some(None),
+                        Some(s) if self_finals.contains(s) => Some(None),
+                        Some(s) => delta(s.clone(), a).map(Some),
+                    };
+                    let Some(target) = target else { continue };
+                    if !states.contains(&target) {
+                        states.push(target.clone());
+                        worklist.push_back(target.clone());
+                    }
+                    table.push((q.clone(), a.clone(), target));
+                }
+            }
+            let mut final_states: Vec<Option<Q>> = states.iter()
+                .filter(|q| q.as_ref().map_or(false, |s| self_finals.contains(s)))
+                .cloned()
+                .collect();
+            if states.contains(&None) {
+                final_states.push(None);
+            }
+            DeterministicFiniteAutomaton::new(
+                states,
+                input_alphabet,
+                move |state: Option<Q>, symbol: &A| {
+                    table.iter()
+                        .find(|(s, a, _)| *s == state && a == symbol)
+                        .map(|(_, _, target)| target.clone())
+                },
+                start,
+                final_states,
+            )
+        }
+
+        // Groups the defined transitions by their `(source, target)` state
+        // indices, collecting the Debug-rendered symbols that drive each one, so
+        // parallel edges between the same pair of states can be merged into a
+        // single DOT edge. Indices are positions into `self.states`.
+        fn transitions_grouped(&self) -> Vec<((usize, usize), Vec<String>)> {
+            let mut groups: Vec<((usize, usize), Vec<String>)> = Vec::new();
+            for (i, q) in self.states.iter().enumerate() {
+                for a in self.input_alphabet.iter() {
+                    if let Some(next) = (self.transition_function)(q.clone(), a) {
+                        if let Some(j) = self.states.iter().position(|s| s == &next) {
+                            let label = format!("{:?}", a);
+                            match groups.iter_mut().find(|(key, _)| *key == (i, j)) {
+                                Some((_, syms)) => syms.push(label),
+                                None => groups.push(((i, j), vec![label])),
+                            }
+                        }
+                    }
+                }
+            }
+            groups
+        }
+
+        // Emits the machine as Graphviz DOT: one node per state (final states as
+        // double circles, the start state marked by an arrow from an invisible
+        // point), and one edge per defined `(state, symbol)` transition, with
+        // parallel edges between the same pair of states merged into a single edge
+        // carrying a comma-separated symbol label.
+        pub fn to_dot(&self) -> String {
+            let mut out = String::from("digraph DFA {\n    rankdir=LR;\n");
+            out.push_str("    __start [shape=point, style=invis];\n");
+            for (i, q) in self.states.iter().enumerate() {
+                let shape = if self.final_states.contains(q) { "doublecircle" } else { "circle" };
+                out.push_str(&format!(
+                    "    n{} [shape={}, label=\"{}\"];\n",
+                    i, shape, dot_escape(&format!("{:?}", q))));
+            }
+            if let Some(s) = self.states.iter().position(|q| q == &self.start_state) {
+                out.push_str(&format!("    __start -> n{};\n", s));
+            }
+            for ((i, j), syms) in self.transitions_grouped() {
+                out.push_str(&format!(
+                    "    n{} -> n{} [label=\"{}\"];\n", i, j, dot_escape(&syms.join(","))));
+            }
+            out.push_str("}\n");
+            out
+        }
+}
+
+
+// An NFA is a 5-tuple (Q,Sigma,Delta,q0,F) like a DFA, except the transition
+// function may send a state and symbol to a *set* of next states, and may also
+// take epsilon (the empty string) in place of a symbol. We model that by letting
+// Delta take an `Option<&A>` — `None` being the epsilon case — and return a
+// `Vec<Q>` of the reachable next states.
+#[derive(Clone)]
+pub struct NondeterministicFiniteAutomaton<
+    Q: Clone + PartialEq + Debug,
+    A: PartialEq + Debug + Clone,
+    F: Fn(Q,Option<&A>) -> Vec<Q> + Clone>{
+    states: Vec<Q>,
+    input_alphabet: Vec<A>,
+    transition_function: F,
+    start_state: Q,
+    final_states: Vec<Q>,
+}
+
+impl<Q,A,F> NondeterministicFiniteAutomaton<Q,A,F>
+where
+Q: Clone + PartialEq + Debug,
+A: PartialEq + Debug + Clone,
+F: Fn(Q,Option<&A>) -> Vec<Q> + Clone
+    {
+        // Sanity checks: q0 is a state and F is a subset of the states (every
+        // final state is asserted to be in Q).
+        pub fn new(    states: Vec<Q>,
+                       input_alphabet: Vec<A>,
+                       transition_function: F,
+                       start_state: Q,
+                       final_states: Vec<Q>,
+        ) -> Self {
+            assert!(states.contains(&start_state));
+            for q in final_states.iter() {
+                assert!(states.contains(q));
+            }
+            Self{
+                states,
+                input_alphabet,
+                transition_function,
+                start_state,
+                final_states,
+            }
+        }
+
+        // The epsilon-closure of a set of states is every state reachable from it
+        // by following any number of epsilon (`None`) transitions.
+        fn epsilon_closure(&self, set: BTreeSet<Q>) -> BTreeSet<Q>
+        where Q: Ord {
+            let mut stack: Vec<Q> = set.iter().cloned().collect();
+            let mut closure = set;
+            while let Some(q) = stack.pop() {
+                for next in (self.transition_function)(q, None) {
+                    // As with the DFA, we have no control over the contents of
+                    // Delta, so we check every state it hands back is really in Q.
+                    assert!(self.states.contains(&next));
+                    if closure.insert(next.clone()) {
+                        stack.push(next);
+                    }
+                }
+            }
+            closure
+        }
+
+        // Subset construction. Each DFA state is a set of NFA states, held in a
+        // `BTreeSet<Q>` so that equal subsets compare equal regardless of the
+        // order they were discovered in. We start from the epsilon-closure of the
+        // start state and, for every subset and alphabet symbol, take the union of
+        // the NFA moves followed by its epsilon-closure; newly-seen subsets are
+        // enqueued on a worklist until no more appear. A DFA subset is final iff it
+        // contains at least one NFA final state.
+        pub fn to_dfa(&self)
+            -> DeterministicFiniteAutomaton<
+                BTreeSet<Q>,
+                A,
+                impl Fn(BTreeSet<Q>,&A) -> Option<BTreeSet<Q>> + Clone>
+        where Q: Ord {
+            let start = self.epsilon_closure(
+                std::iter::once(self.start_state.clone()).collect());
+            let mut states: Vec<BTreeSet<Q>> = vec![start.clone()];
+            let mut worklist: VecDeque<BTreeSet<Q>> = VecDeque::from([start.clone()]);
+            let mut table: Vec<(BTreeSet<Q>, A, BTreeSet<Q>)> = Vec::new();
+            while let Some(subset) = worklist.pop_front() {
+                for a in self.input_alphabet.iter() {
+                    let mut moved: BTreeSet<Q> = BTreeSet::new();
+                    for q in subset.iter() {
+                        for next in (self.transition_function)(q.clone(), Some(a)) {
+                            assert!(self.states.contains(&next));
+                            moved.insert(next);
+                        }
+                    }
+                    let target = self.epsilon_closure(moved);
+                    // An empty target means there is no move on `a`; we leave the
+                    // DFA transition undefined so `is_accepted` rejects there, just
+                    // like the hand-written partial machines.
+                    if target.is_empty() {
+                        continue;
+                    }
+                    if !states.contains(&target) {
+                        states.push(target.clone());
+                        worklist.push_back(target.clone());
+                    }
+                    table.push((subset.clone(), a.clone(), target));
+                }
+            }
+            let final_states: Vec<BTreeSet<Q>> = states.iter()
+                .filter(|s| s.iter().any(|q| self.final_states.contains(q)))
+                .cloned()
+                .collect();
+            let input_alphabet = self.input_alphabet.clone();
+            DeterministicFiniteAutomaton::new(
+                states,
+                input_alphabet,
+                move |state: BTreeSet<Q>, symbol: &A| {
+                    table.iter()
+                        .find(|(s,a,_)| *s == state && a == symbol)
+                        .map(|(_,_,target)| target.clone())
+                },
+                start,
+                final_states,
+            )
+        }
+}
+
+// Escapes the characters that are special inside a DOT double-quoted string, so
+// that state and symbol labels (which come from `Debug`, and often contain
+// quotes) survive into valid DOT.
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+
+// A finite state transducer generalises the acceptor: like a DFA it is a
+// 5-tuple, but its transition function emits an output value along every edge as
+// well as moving to the next state — `Fn(Q,&A) -> Option<(Q, O)>`. Running an
+// input string therefore produces a sequence of outputs in addition to the
+// accept/reject verdict, giving a Mealy-style machine suitable for tokenising,
+// transliterating, or otherwise rewriting a stream. The output type `O` only
+// appears through the transition function, so we carry it with a `PhantomData`.
+#[derive(Clone)]
+pub struct Transducer<
+    Q: Clone + PartialEq + Debug,
+    A: PartialEq + Debug + Clone,
+    O: Clone + Debug,
+    F: Fn(Q,&A) -> Option<(Q,O)> + Clone>{
+    states: Vec<Q>,
+    input_alphabet: Vec<A>,
+    transition_function: F,
+    start_state: Q,
+    final_states: Vec<Q>,
+    output: std::marker::PhantomData<O>,
+}
+
+impl<Q,A,O,F> Transducer<Q,A,O,F>
+where
+Q: Clone + PartialEq + Debug,
+A: PartialEq + Debug + Clone,
+O: Clone + Debug,
+F: Fn(Q,&A) -> Option<(Q,O)> + Clone
+    {
+        // Sanity checks: q0 is a state and F is a subset of the states (every
+        // final state is asserted to be in Q).
+        pub fn new(    states: Vec<Q>,
+                       input_alphabet: Vec<A>,
+                       transition_function: F,
+                       start_state: Q,
+                       final_states: Vec<Q>,
+        ) -> Self {
+            assert!(states.contains(&start_state));
+            for q in final_states.iter() {
+                assert!(states.contains(q));
+            }
+            Self{
+                states,
+                input_alphabet,
+                transition_function,
+                start_state,
+                final_states,
+                output: std::marker::PhantomData,
+            }
+        }
+
+        // Runs the machine over `input`, threading the state exactly as
+        // `is_accepted` does and concatenating the output emitted on each edge.
+        // Returns `Some(outputs)` only when every transition along the way is
+        // defined and the run ends in a final state; any undefined transition or a
+        // non-final end state yields `None`.
+        pub fn transduce(&self, input: Vec<A>) -> Option<Vec<O>> {
+            for a in input.iter() {
+                assert!(self.input_alphabet.contains(a));
+            }
+            let mut state = self.start_state.clone();
+            let mut outputs: Vec<O> = Vec::new();
+            for symbol in input.iter() {
+                match (self.transition_function)(state.clone(), symbol) {
+                    // As in the DFA, we cannot trust F, so we check the returned
+                    // state really is an element of Q.
+                    Some((next_state, out)) => {
+                        assert!(self.states.contains(&next_state));
+                        state = next_state;
+                        outputs.push(out);
+                    }
+                    None => return None,
+                }
+            }
+            if self.final_states.contains(&state) {
+                Some(outputs)
+            } else {
+                None
+            }
+        }
+}
+
+
+// A Levenshtein automaton: a DFA that accepts exactly the strings within edit
+// distance `k` (insertions, deletions, substitutions) of a fixed `query`. Rather
+// than enumerate the infinitely many input strings, we take the states to be the
+// rows of the edit-distance dynamic-programming table. A row is the vector of
+// distances `d[0..=m]` between the input read so far and each prefix of the query
+// (`m` being the query length). Clamping every entry to `k+1` — our stand-in for
+// "infinity" — makes the set of reachable rows finite, so this really is a DFA.
+//
+// The start row is `[0,1,2,...,m]` (the distances from the empty input to each
+// query prefix). Reading a symbol advances the row by the usual recurrence, and a
+// row accepts when its last cell is `<= k`. Any row whose minimum entry already
+// exceeds `k` can never recover, so we prune it: it never becomes a state, the
+// transition into it is simply left undefined, and `is_accepted` rejects there,
+// exactly like the hand-written partial machines elsewhere in this file.
+pub fn levenshtein_automaton<A>(
+    query: Vec<A>,
+    k: usize,
+    input_alphabet: Vec<A>,
+) -> DeterministicFiniteAutomaton<
+        Vec<usize>,
+        A,
+        impl Fn(Vec<usize>,&A) -> Option<Vec<usize>> + Clone>
+where A: PartialEq + Debug + Clone {
+    let m = query.len();
+    let inf = k + 1; // the clamped "infinity"
+    // The next row given the previous row and the consumed symbol.
+    let step = |prev: &Vec<usize>, symbol: &A| -> Vec<usize> {
+        let mut next = vec![0usize; m + 1];
+        // d[0] is the cost of deleting every input char read so far.
+        next[0] = (prev[0] + 1).min(inf);
+        for j in 1..=m {
+            let sub = prev[j - 1] + if query[j - 1] != *symbol { 1 } else { 0 };
+            next[j] = (next[j - 1] + 1).min(prev[j] + 1).min(sub).min(inf);
+        }
+        next
+    };
+
+    let start: Vec<usize> = (0..=m).map(|j| j.min(inf)).collect();
+    let mut states: Vec<Vec<usize>> = vec![start.clone()];
+    let mut worklist: VecDeque<Vec<usize>> = VecDeque::from([start.clone()]);
+    let mut table: Vec<(Vec<usize>, A, Vec<usize>)> = Vec::new();
+    while let Some(row) = worklist.pop_front() {
+        for a in input_alphabet.iter() {
+            let next = step(&row, a);
+            // A row that is "infinity" everywhere is dead; leave the transition
+            // undefined so the string is rejected.
+            if next.iter().min().copied().unwrap_or(inf) > k {
+                continue;
+            }
+            if !states.contains(&next) {
+                states.push(next.clone());
+                worklist.push_back(next.clone());
+            }
+            table.push((row.clone(), a.clone(), next));
+        }
+    }
+    let final_states: Vec<Vec<usize>> = states.iter()
+        .filter(|row| row[m] <= k)
+        .cloned()
+        .collect();
+    DeterministicFiniteAutomaton::new(
+        states,
+        input_alphabet,
+        move |state: Vec<usize>, symbol: &A| {
+            table.iter()
+                .find(|(s, a, _)| *s == state && a == symbol)
+                .map(|(_, _, target)| target.clone())
+        },
+        start,
+        final_states,
+    )
+}
+
+
+// The syntax tree of the small regex dialect understood by `from_regex`:
+// literals, concatenation, `|` alternation, and the `*` / `+` / `?` postfix
+// operators, with parentheses for grouping. `Empty` is the tree of the empty
+// string, produced by e.g. an empty alternative like `a|`.
+#[derive(Clone, Debug)]
+enum Regex {
+    Empty,
+    Literal(char),
+    Concat(Box<Regex>, Box<Regex>),
+    Alternate(Box<Regex>, Box<Regex>),
+    Star(Box<Regex>),
+    Plus(Box<Regex>),
+    Question(Box<Regex>),
+}
+
+// A hand-written recursive-descent parser for the dialect. The grammar is the
+// usual precedence ladder: alternation binds loosest, then concatenation, then
+// the postfix repetition operators, then atoms (a literal or a parenthesised
+// sub-expression). There is no escaping — every character that is not one of the
+// metacharacters `()|*+?` is a literal.
+struct RegexParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl RegexParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.pos += 1;
+        }
+        c
+    }
+
+    // alternation := concatenation ('|' concatenation)*
+    fn alternation(&mut self) -> Regex {
+        let mut left = self.concatenation();
+        while self.peek() == Some('|') {
+            self.next();
+            let right = self.concatenation();
+            left = Regex::Alternate(Box::new(left), Box::new(right));
+        }
+        left
+    }
+
+    // concatenation := repetition*
+    fn concatenation(&mut self) -> Regex {
+        let mut node: Option<Regex> = None;
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let factor = self.repetition();
+            node = Some(match node {
+                None => factor,
+                Some(left) => Regex::Concat(Box::new(left), Box::new(factor)),
+            });
+        }
+        node.unwrap_or(Regex::Empty)
+    }
+
+    // repetition := atom ('*' | '+' | '?')*
+    fn repetition(&mut self) -> Regex {
+        let mut atom = self.atom();
+        while let Some(c) = self.peek() {
+            atom = match c {
+                '*' => Regex::Star(Box::new(atom)),
+                '+' => Regex::Plus(Box::new(atom)),
+                '?' => Regex::Question(Box::new(atom)),
+                _ => break,
+            };
+            self.next();
+        }
+        atom
+    }
+
+    // atom := '(' alternation ')' | literal
+    fn atom(&mut self) -> Regex {
+        match self.next() {
+            Some('(') => {
+                let inner = self.alternation();
+                assert_eq!(self.next(), Some(')'), "unbalanced parentheses in regex");
+                inner
+            }
+            Some(c) => Regex::Literal(c),
+            None => Regex::Empty,
+        }
+    }
+}
+
+// Thompson's construction. We allocate fresh NFA states as plain integers and
+// record every edge — `None` being an epsilon edge — in a flat list, returning
+// the `(start, accept)` pair of states for each fragment. The recursive cases are
+// the textbook ones: a literal is a single labelled edge; concatenation bridges
+// the first fragment's accept to the second's start with an epsilon edge;
+// alternation introduces a fresh start and accept wired to both fragments; and
+// the `*` / `+` / `?` operators add the standard epsilon back- and forward-edges.
+struct Thompson {
+    edges: Vec<(usize, Option<char>, usize)>,
+    next: usize,
+}
+
+impl Thompson {
+    fn state(&mut self) -> usize {
+        let s = self.next;
+        self.next += 1;
+        s
+    }
+
+    fn edge(&mut self, from: usize, label: Option<char>, to: usize) {
+        self.edges.push((from, label, to));
+    }
+
+    fn build(&mut self, regex: &Regex) -> (usize, usize) {
+        match regex {
+            Regex::Empty => {
+                let s = self.state();
+                let f = self.state();
+                self.edge(s, None, f);
+                (s, f)
+            }
+            Regex::Literal(c) => {
+                let s = self.state();
+                let f = self.state();
+                self.edge(s, Some(*c), f);
+                (s, f)
+            }
+            Regex::Concat(a, b) => {
+                let (sa, fa) = self.build(a);
+                let (sb, fb) = self.build(b);
+                self.edge(fa, None, sb);
+                (sa, fb)
+            }
+            Regex::Alternate(a, b) => {
+                let s = self.state();
+                let f = self.state();
+                let (sa, fa) = self.build(a);
+                let (sb, fb) = self.build(b);
+                self.edge(s, None, sa);
+                self.edge(s, None, sb);
+                self.edge(fa, None, f);
+                self.edge(fb, None, f);
+                (s, f)
+            }
+            Regex::Star(a) => {
+                let s = self.state();
+                let f = self.state();
+                let (sa, fa) = self.build(a);
+                self.edge(s, None, sa);
+                self.edge(s, None, f);
+                self.edge(fa, None, sa);
+                self.edge(fa, None, f);
+                (s, f)
+            }
+            Regex::Plus(a) => {
+                let s = self.state();
+                let f = self.state();
+                let (sa, fa) = self.build(a);
+                self.edge(s, None, sa);
+                self.edge(fa, None, sa);
+                self.edge(fa, None, f);
+                (s, f)
+            }
+            Regex::Question(a) => {
+                let s = self.state();
+                let f = self.state();
+                let (sa, fa) = self.build(a);
+                self.edge(s, None, sa);
+                self.edge(s, None, f);
+                self.edge(fa, None, f);
+                (s, f)
+            }
+        }
+    }
 }
 
+// Compile a regex pattern into a DFA. We parse the pattern, build an epsilon-NFA
+// with Thompson's construction, and then determinise it with the subset
+// construction from `to_dfa`, so the result is an ordinary
+// `DeterministicFiniteAutomaton` over `char` that runs through `is_accepted`.
+// DFA states are the subsets of NFA states the subset construction produces.
+pub fn from_regex(pattern: &str)
+    -> DeterministicFiniteAutomaton<
+        BTreeSet<usize>,
+        char,
+        impl Fn(BTreeSet<usize>,&char) -> Option<BTreeSet<usize>> + Clone> {
+    let mut parser = RegexParser { chars: pattern.chars().collect(), pos: 0 };
+    let tree = parser.alternation();
+    assert_eq!(parser.pos, parser.chars.len(), "unexpected trailing input in regex");
+
+    let mut thompson = Thompson { edges: Vec::new(), next: 0 };
+    let (start, accept) = thompson.build(&tree);
+    let edges = thompson.edges;
+    let states: Vec<usize> = (0..thompson.next).collect();
+
+    // The alphabet is the set of literal symbols that actually appear on an edge.
+    let mut input_alphabet: Vec<char> = Vec::new();
+    for (_, label, _) in edges.iter() {
+        if let Some(c) = label {
+            if !input_alphabet.contains(c) {
+                input_alphabet.push(*c);
+            }
+        }
+    }
+
+    let nfa = NondeterministicFiniteAutomaton::new(
+        states,
+        input_alphabet,
+        move |q: usize, symbol: Option<&char>| {
+            edges.iter()
+                .filter(|(from, label, _)| *from == q && label.as_ref() == symbol)
+                .map(|(_, _, to)| *to)
+                .collect()
+        },
+        start,
+        vec![accept],
+    );
+    nfa.to_dfa()
+}
 
 
 #[cfg(test)]
@@ -208,5 +1184,271 @@ mod tests {
         assert_eq!(dfa.is_accepted(vec!['a','b','a','b']).print_and_accept(),false);
     }
 
+    // An NFA over {0,1} whose language is the strings that contain "01" as a
+    // substring. State 1 loops on everything, guesses the "01" on 0->1, and
+    // state 3 loops forever once the pattern has been seen.
+    #[test]
+    fn nfa_contains_zero_one() {
+        fn transition_function(q:i32,a:Option<&i32>) -> Vec<i32> {
+            match (q,a) {
+                (1, Some(0)) => vec![1,2],
+                (1, Some(1)) => vec![1],
+                (2, Some(1)) => vec![3],
+                (3, Some(0)) => vec![3],
+                (3, Some(1)) => vec![3],
+                (_, _) => vec![],
+            }
+        }
+        let nfa = NondeterministicFiniteAutomaton::new(
+            vec![1,2,3],
+            vec![0,1],
+            transition_function,
+            1,
+            vec![3],
+        );
+        let dfa = nfa.to_dfa();
+        assert!(dfa.is_accepted(vec![0,1]).print_and_accept());
+        assert!(dfa.is_accepted(vec![1,1,0,0,1,0]).print_and_accept());
+        assert_eq!(dfa.is_accepted(vec![0,0,0]).print_and_accept(),false);
+        assert_eq!(dfa.is_accepted(vec![1,1,1]).print_and_accept(),false);
+        assert_eq!(dfa.is_accepted(vec![]).print_and_accept(),false);
+    }
+
+    // A four-state machine for "strings ending in 1" in which states B,D (just
+    // read a 1) are equivalent and A,C (start / just read a 0) are equivalent,
+    // so minimization should collapse it to two states while preserving the
+    // language.
+    #[test]
+    fn minimize_ends_in_one() {
+        fn transition_function(q:&'static str,a:&i32) -> Option<&'static str> {
+            match (q,a) {
+                ("A",1) => Some("B"), ("A",0) => Some("C"),
+                ("B",1) => Some("D"), ("B",0) => Some("C"),
+                ("C",1) => Some("B"), ("C",0) => Some("C"),
+                ("D",1) => Some("B"), ("D",0) => Some("C"),
+                (_,_) => None,
+            }
+        }
+        let dfa = DeterministicFiniteAutomaton::new(
+            vec!["A","B","C","D"],
+            vec![0,1],
+            transition_function,
+            "A",
+            vec!["B","D"],
+        );
+        let min = dfa.minimize();
+        assert_eq!(min.states.len(), 2);
+        for input in [vec![1], vec![0,1], vec![1,1], vec![0,0,1]] {
+            assert!(min.is_accepted(input.clone()).print_and_accept());
+            assert!(dfa.is_accepted(input).print_and_accept());
+        }
+        for input in [vec![], vec![0], vec![1,0], vec![1,1,0]] {
+            assert_eq!(min.is_accepted(input.clone()).print_and_accept(), false);
+            assert_eq!(dfa.is_accepted(input).print_and_accept(), false);
+        }
+    }
+
+    // A Levenshtein automaton for "cat" with k = 1 accepts exactly the strings
+    // within one edit of "cat": the word itself, single substitutions, single
+    // deletions, and single insertions, while rejecting anything two or more
+    // edits away.
+    #[test]
+    fn levenshtein_cat() {
+        let alphabet = vec!['c','a','t','r','s'];
+        let dfa = levenshtein_automaton("cat".chars().collect(), 1, alphabet);
+        // Distance 0 and 1.
+        assert!(dfa.is_accepted("cat".chars().collect()).print_and_accept());
+        assert!(dfa.is_accepted("rat".chars().collect()).print_and_accept()); // substitute
+        assert!(dfa.is_accepted("at".chars().collect()).print_and_accept());  // delete
+        assert!(dfa.is_accepted("cart".chars().collect()).print_and_accept()); // insert
+        assert!(dfa.is_accepted("ca".chars().collect()).print_and_accept());  // delete
+        // Distance 2 or more.
+        assert_eq!(dfa.is_accepted("rar".chars().collect()).print_and_accept(), false);
+        assert_eq!(dfa.is_accepted("".chars().collect()).print_and_accept(), false);
+        assert_eq!(dfa.is_accepted("carts".chars().collect()).print_and_accept(), false);
+    }
+
+    // "strings that end in 1": `b` is the state reached after reading a 1.
+    fn ends_in_one() -> DeterministicFiniteAutomaton<
+            &'static str, i32, impl Fn(&'static str,&i32) -> Option<&'static str> + Clone> {
+        DeterministicFiniteAutomaton::new(
+            vec!["a","b"],
+            vec![0,1],
+            |q:&'static str, a:&i32| match (q,a) {
+                ("a",1) => Some("b"), ("a",0) => Some("a"),
+                ("b",1) => Some("b"), ("b",0) => Some("a"),
+                (_,_) => None,
+            },
+            "a",
+            vec!["b"],
+        )
+    }
+
+    // "strings of even length": `e` is the even-length (and start) state.
+    fn even_length() -> DeterministicFiniteAutomaton<
+            &'static str, i32, impl Fn(&'static str,&i32) -> Option<&'static str> + Clone> {
+        DeterministicFiniteAutomaton::new(
+            vec!["e","o"],
+            vec![0,1],
+            |q:&'static str, _a:&i32| match q {
+                "e" => Some("o"),
+                "o" => Some("e"),
+                _ => None,
+            },
+            "e",
+            vec!["e"],
+        )
+    }
+
+    #[test]
+    fn intersection_ends_one_and_even() {
+        let dfa = ends_in_one().intersection(&even_length());
+        assert!(dfa.is_accepted(vec![0,1]).print_and_accept());
+        assert!(dfa.is_accepted(vec![0,0,1,1]).print_and_accept());
+        assert_eq!(dfa.is_accepted(vec![1]).print_and_accept(), false);        // odd length
+        assert_eq!(dfa.is_accepted(vec![1,0]).print_and_accept(), false);      // ends in 0
+        assert_eq!(dfa.is_accepted(vec![]).print_and_accept(), false);
+    }
+
+    #[test]
+    fn union_ends_one_or_even() {
+        let dfa = ends_in_one().union(&even_length());
+        assert!(dfa.is_accepted(vec![1]).print_and_accept());        // ends in 1
+        assert!(dfa.is_accepted(vec![0,0]).print_and_accept());      // even length
+        assert!(dfa.is_accepted(vec![]).print_and_accept());         // even length (0)
+        assert_eq!(dfa.is_accepted(vec![0]).print_and_accept(), false); // odd, ends in 0
+    }
+
+    #[test]
+    fn complement_ends_in_one() {
+        let dfa = ends_in_one().complement();
+        assert!(dfa.is_accepted(vec![]).print_and_accept());
+        assert!(dfa.is_accepted(vec![0]).print_and_accept());
+        assert!(dfa.is_accepted(vec![1,0]).print_and_accept());
+        assert_eq!(dfa.is_accepted(vec![1]).print_and_accept(), false);
+        assert_eq!(dfa.is_accepted(vec![0,1]).print_and_accept(), false);
+    }
+
+    #[test]
+    fn starts_with_ab() {
+        // A machine accepting exactly the string "ab".
+        let exact = DeterministicFiniteAutomaton::new(
+            vec!["s","x","f"],
+            vec!['a','b'],
+            |q:&'static str, a:&char| match (q,a) {
+                ("s",'a') => Some("x"),
+                ("x",'b') => Some("f"),
+                (_,_) => None,
+            },
+            "s",
+            vec!["f"],
+        );
+        let dfa = exact.starts_with();
+        assert!(dfa.is_accepted(vec!['a','b']).print_and_accept());
+        assert!(dfa.is_accepted(vec!['a','b','a','b']).print_and_accept());
+        assert!(dfa.is_accepted(vec!['a','b','b','a']).print_and_accept());
+        assert_eq!(dfa.is_accepted(vec!['a']).print_and_accept(), false);
+        assert_eq!(dfa.is_accepted(vec!['b','a']).print_and_accept(), false);
+    }
+
+    // `a(b|c)*d` — an `a`, any number of `b`s and `c`s, then a `d`.
+    #[test]
+    fn regex_a_bc_star_d() {
+        let dfa = from_regex("a(b|c)*d");
+        assert!(dfa.is_accepted("ad".chars().collect()).print_and_accept());
+        assert!(dfa.is_accepted("abd".chars().collect()).print_and_accept());
+        assert!(dfa.is_accepted("acbbccd".chars().collect()).print_and_accept());
+        assert_eq!(dfa.is_accepted("a".chars().collect()).print_and_accept(), false);
+        assert_eq!(dfa.is_accepted("bd".chars().collect()).print_and_accept(), false);
+        assert_eq!(dfa.is_accepted("".chars().collect()).print_and_accept(), false);
+    }
+
+    // The `+` and `?` operators: `(ab)+c?` — one or more `ab`s and an optional `c`.
+    #[test]
+    fn regex_plus_and_question() {
+        let dfa = from_regex("(ab)+c?");
+        assert!(dfa.is_accepted("ab".chars().collect()).print_and_accept());
+        assert!(dfa.is_accepted("abc".chars().collect()).print_and_accept());
+        assert!(dfa.is_accepted("abab".chars().collect()).print_and_accept());
+        assert!(dfa.is_accepted("ababc".chars().collect()).print_and_accept());
+        assert_eq!(dfa.is_accepted("".chars().collect()).print_and_accept(), false);
+        assert_eq!(dfa.is_accepted("abcc".chars().collect()).print_and_accept(), false);
+        assert_eq!(dfa.is_accepted("c".chars().collect()).print_and_accept(), false);
+    }
+
+    #[test]
+    fn dot_export() {
+        let dfa = DeterministicFiniteAutomaton::new(
+            vec!["a","b"],
+            vec![0,1],
+            |q:&'static str, a:&i32| match (q,a) {
+                ("a",1) => Some("b"), ("a",0) => Some("a"),
+                ("b",1) => Some("b"), ("b",0) => Some("a"),
+                (_,_) => None,
+            },
+            "a",
+            vec!["b"],
+        );
+        let dot = dfa.to_dot();
+        assert!(dot.starts_with("digraph DFA {"));
+        // "b" is final, so it is drawn as a double circle.
+        assert!(dot.contains("shape=doublecircle"));
+        // The start arrow comes from the invisible node.
+        assert!(dot.contains("__start -> n0;"));
+        // The two transitions into "a" (on 0) come from different states, so each
+        // is drawn as its own edge rather than merged.
+        assert!(dot.contains("n0 -> n0 [label=\"0\"];"));
+        assert!(dot.contains("n1 -> n0 [label=\"0\"];"));
+
+        // The trace export captions the verdict and highlights the run in red.
+        let trace = dfa.is_accepted(vec![0,1]).to_dot();
+        assert!(trace.contains("label=\"accepted\";"));
+        assert!(trace.contains("color=red"));
+        assert!(trace.contains("fillcolor=lightblue"));
+        let reject = dfa.is_accepted(vec![1,0]).to_dot();
+        assert!(reject.contains("label=\"rejected\";"));
+    }
+
+    // A transducer over {0,1} that flips every bit, accepting in either state so
+    // every input is transduced. It emits one output symbol per input symbol.
+    #[test]
+    fn transduce_bit_flip() {
+        let fst = Transducer::new(
+            vec!["even","odd"],
+            vec![0,1],
+            |q:&'static str, a:&i32| match (q,a) {
+                ("even",0) => Some(("odd",1)),
+                ("even",1) => Some(("odd",0)),
+                ("odd",0) => Some(("even",1)),
+                ("odd",1) => Some(("even",0)),
+                (_,_) => None,
+            },
+            "even",
+            vec!["even","odd"],
+        );
+        assert_eq!(fst.transduce(vec![0,1,1,0]), Some(vec![1,0,0,1]));
+        assert_eq!(fst.transduce(vec![]), Some(vec![]));
+    }
+
+    // A transducer whose run can fail: it only accepts a run that ends on a `1`
+    // (state "b"), and has no transition on a symbol outside {0,1}'s defined
+    // moves, so a non-final end state returns None.
+    #[test]
+    fn transduce_rejects_non_final() {
+        let fst = Transducer::new(
+            vec!["a","b"],
+            vec![0,1],
+            |q:&'static str, a:&i32| match (q,a) {
+                ("a",1) => Some(("b",1)), ("a",0) => Some(("a",0)),
+                ("b",1) => Some(("b",1)), ("b",0) => Some(("a",0)),
+                (_,_) => None,
+            },
+            "a",
+            vec!["b"],
+        );
+        assert_eq!(fst.transduce(vec![0,0,1]), Some(vec![0,0,1]));
+        // Ends on "a" (last symbol 0), which is not final.
+        assert_eq!(fst.transduce(vec![1,0]), None);
+    }
 
 }
\ No newline at end of file